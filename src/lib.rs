@@ -3,17 +3,21 @@
 
 // future features:
 // - follow or ignore symlinks
-// - exclude patterns
-// - sort by size, modified date or name (option for dirs first)
 
 use chrono::{DateTime, Utc};
 use clap::Parser;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashSet};
 use std::error::Error;
 use std::fmt;
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::time;
 use std::vec;
+use terminal_size::{terminal_size, Width};
 
 #[derive(Parser)]
 #[command(version = "0.1")]
@@ -43,10 +47,71 @@ pub struct Cli {
     #[arg(long)]
     skip_symlinks: bool,
 
+    /// report actual disk usage (allocated blocks) instead of apparent size
+    #[arg(short = 'u', long)]
+    usage: bool,
+
+    /// skip entries whose name matches GLOB (may be given multiple times)
+    #[arg(short = 'x', long, value_name = "GLOB", value_parser = parse_exclude_glob)]
+    exclude: Vec<Glob>,
+
+    /// skip hidden files and directories
+    #[arg(short = 'H', long = "no-hidden")]
+    no_hidden: bool,
+
+    /// key to sort entries by
+    #[arg(long, value_enum, default_value = "size")]
+    sort: SortKey,
+
+    /// list directories ahead of files within each directory
+    #[arg(long)]
+    dirs_first: bool,
+
+    /// render a horizontal bar alongside each entry, proportional to its
+    /// share of its parent directory
+    #[arg(long)]
+    bars: bool,
+
+    /// collapse entries smaller than SIZE (e.g. 512, 10K, 4M, 1G) within a
+    /// directory into a single summary entry
+    #[arg(short = 'A', long, value_name = "SIZE", value_parser = parse_size_threshold)]
+    aggregate: Option<u64>,
+
+    /// look inside .tar, .tar.gz/.tgz and .tar.zst archives as if they were directories
+    #[arg(long)]
+    archives: bool,
+
     /// file or path
     file: String,
 }
 
+fn parse_size_threshold(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (digits, multiplier) = match s.chars().last() {
+        Some(c) if c.eq_ignore_ascii_case(&'k') => (&s[..s.len() - 1], 1024),
+        Some(c) if c.eq_ignore_ascii_case(&'m') => (&s[..s.len() - 1], 1024 * 1024),
+        Some(c) if c.eq_ignore_ascii_case(&'g') => (&s[..s.len() - 1], 1024 * 1024 * 1024),
+        _ => (s, 1),
+    };
+
+    digits
+        .trim()
+        .parse::<u64>()
+        .map(|n| n * multiplier)
+        .map_err(|_| format!("invalid size threshold '{}', expected e.g. 10K, 4M, 1G", s))
+}
+
+fn parse_exclude_glob(s: &str) -> Result<Glob, String> {
+    Glob::new(s).map_err(|e| format!("invalid --exclude pattern '{}': {}", s, e))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortKey {
+    Size,
+    Time,
+    Name,
+}
+
 pub enum ByteType {
     Binary,
     Decimal,
@@ -57,6 +122,9 @@ pub enum ItemType {
     File,
     Dir,
     Symlink,
+    Archive,
+    ArchiveMember,
+    ArchiveMemberDir,
 }
 
 #[derive(Debug, PartialEq, PartialOrd, Ord, Eq)]
@@ -67,6 +135,10 @@ enum ItemTypeColours {
     DirHidden = 74,
     Symlink = 10,
     SymlinkHidden = 70,
+    Archive = 214,
+    ArchiveHidden = 94,
+    ArchiveMember = 180,
+    ArchiveMemberHidden = 100,
 }
 
 #[derive(Debug, PartialEq, PartialOrd, Ord, Eq)]
@@ -78,6 +150,7 @@ pub struct FileInfo {
     pub modified: time::SystemTime,
     pub children: Option<Vec<FileInfo>>,
     pub accessible: bool,
+    pub is_duplicate_inode: bool,
 }
 
 impl FileInfo {
@@ -125,6 +198,20 @@ impl FileInfo {
                     ItemTypeColours::File
                 }
             }
+            ItemType::Archive => {
+                if hidden {
+                    ItemTypeColours::ArchiveHidden
+                } else {
+                    ItemTypeColours::Archive
+                }
+            }
+            ItemType::ArchiveMember | ItemType::ArchiveMemberDir => {
+                if hidden {
+                    ItemTypeColours::ArchiveMemberHidden
+                } else {
+                    ItemTypeColours::ArchiveMember
+                }
+            }
         };
 
         s = format!("\x1b[38;5;{}m{:#}\x1b[0m", colour as i32, s);
@@ -195,12 +282,32 @@ fn format_size(size: u64, byte_type: &ByteType) -> String {
     format!("{0:7.3} {1}B", size_f, prefixes[idx])
 }
 
+fn bar_width() -> usize {
+    let term_width = match terminal_size() {
+        Some((Width(w), _)) => w as usize,
+        None => 80,
+    };
+    term_width.saturating_sub(40).clamp(10, 40)
+}
+
+fn render_bar(size: u64, parent_size: u64, width: usize) -> String {
+    let frac = if parent_size == 0 {
+        0.0
+    } else {
+        size as f64 / parent_size as f64
+    };
+    let filled = ((frac * width as f64).round() as usize).min(width);
+    format!("[{}{}]", "#".repeat(filled), " ".repeat(width - filled))
+}
+
 fn print_results(
     path_info: &Vec<FileInfo>,
     humanize: bool,
     si: bool,
     show_ts: bool,
     max_depth: Option<u8>,
+    bars: bool,
+    parent_size: Option<u64>,
 ) {
     let byte_type = if si {
         ByteType::Decimal
@@ -215,9 +322,18 @@ fn print_results(
             }
         }
         let s = info.to_string(humanize, &byte_type, show_ts);
-        println!("{}", s);
+        if bars {
+            if let Some(ps) = parent_size {
+                let bar = render_bar(info.size, ps, bar_width());
+                println!("{} {}", bar, s);
+            } else {
+                println!("{}", s);
+            }
+        } else {
+            println!("{}", s);
+        }
         if let Some(v) = &info.children {
-            print_results(v, humanize, si, show_ts, max_depth)
+            print_results(v, humanize, si, show_ts, max_depth, bars, Some(info.size))
         }
     }
 }
@@ -235,11 +351,49 @@ fn get_file_type(path: &Path) -> ItemType {
     }
 }
 
+#[cfg(unix)]
+fn disk_usage(md: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    md.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn disk_usage(md: &fs::Metadata) -> u64 {
+    md.len()
+}
+
+fn file_size(md: &fs::Metadata, usage: bool) -> u64 {
+    if usage {
+        disk_usage(md)
+    } else {
+        md.len()
+    }
+}
+
+// `seen` is mutex-guarded because directories are walked in parallel. Which
+// hard-linked sibling is first to insert its (dev, ino) — and so which
+// ancestor directory's total the shared size is credited to — is whichever
+// thread wins the lock race, not traversal order; this is best-effort and
+// not guaranteed to be stable between runs.
+#[cfg(unix)]
+fn is_duplicate_inode(md: &fs::Metadata, seen: &Mutex<HashSet<(u64, u64)>>) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    let key = (md.dev(), md.ino());
+    !seen.lock().unwrap().insert(key)
+}
+
+#[cfg(not(unix))]
+fn is_duplicate_inode(_md: &fs::Metadata, _seen: &Mutex<HashSet<(u64, u64)>>) -> bool {
+    false
+}
+
 fn get_file_info(
     path: &Path,
     depth: u8,
     md: &fs::Metadata,
     parent_is_symlink: bool,
+    usage: bool,
+    seen: &Mutex<HashSet<(u64, u64)>>,
 ) -> Result<FileInfo, Box<dyn Error>> {
     let modified = md.modified()?;
     // make new PathBuf from given Path (to avoid lifetime issues)
@@ -257,22 +411,279 @@ fn get_file_info(
         path: p,
         depth: depth,
         file_type: ft,
-        size: md.len(),
+        size: file_size(md, usage),
         modified: modified.clone(),
         children: None,
         accessible: true,
+        is_duplicate_inode: is_duplicate_inode(md, seen),
     })
 }
 
-pub fn walk(
+fn is_excluded(path: &Path, no_hidden: bool, excludes: &Option<GlobSet>) -> bool {
+    let name = match path.file_name().and_then(|n| n.to_str()) {
+        Some(name) => name,
+        None => return false,
+    };
+
+    if no_hidden && name.starts_with('.') {
+        return true;
+    }
+
+    match excludes {
+        Some(set) => set.is_match(name),
+        None => false,
+    }
+}
+
+enum ArchiveKind {
+    Tar,
+    TarGz,
+    TarZst,
+}
+
+fn archive_kind(path: &Path) -> Option<ArchiveKind> {
+    let name = path.file_name()?.to_str()?;
+    if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(ArchiveKind::TarGz)
+    } else if name.ends_with(".tar.zst") {
+        Some(ArchiveKind::TarZst)
+    } else if name.ends_with(".tar") {
+        Some(ArchiveKind::Tar)
+    } else {
+        None
+    }
+}
+
+// tar entries are flat paths (e.g. "a/b/c.txt"); this rebuilds the directory
+// structure they imply.
+enum ArchiveNode {
+    File {
+        size: u64,
+        modified: time::SystemTime,
+    },
+    Dir(BTreeMap<String, ArchiveNode>),
+}
+
+fn insert_archive_entry(
+    root: &mut BTreeMap<String, ArchiveNode>,
+    components: &[String],
+    size: u64,
+    modified: time::SystemTime,
+    is_dir: bool,
+) {
+    let (head, rest) = match components.split_first() {
+        Some(parts) => parts,
+        None => return,
+    };
+
+    if rest.is_empty() {
+        if is_dir {
+            // A directory's own tar header (e.g. "adir/") carries no children
+            // of its own; just make sure the node exists as a Dir so later
+            // entries for "adir/child" can descend into it.
+            root.entry(head.clone())
+                .or_insert_with(|| ArchiveNode::Dir(BTreeMap::new()));
+        } else {
+            use std::collections::btree_map::Entry;
+            if let Entry::Vacant(e) = root.entry(head.clone()) {
+                e.insert(ArchiveNode::File { size, modified });
+            }
+        }
+    } else if let ArchiveNode::Dir(children) = root
+        .entry(head.clone())
+        .or_insert_with(|| ArchiveNode::Dir(BTreeMap::new()))
+    {
+        insert_archive_entry(children, rest, size, modified, is_dir);
+    }
+}
+
+fn archive_node_to_file_info(
+    name: &str,
+    node: ArchiveNode,
+    parent: &Path,
+    depth: u8,
+    sort_key: SortKey,
+    sort_ascending: bool,
+    dirs_first: bool,
+) -> FileInfo {
+    let path = parent.join(name);
+    match node {
+        ArchiveNode::File { size, modified } => FileInfo {
+            path,
+            depth,
+            file_type: ItemType::ArchiveMember,
+            size,
+            modified,
+            children: None,
+            accessible: true,
+            is_duplicate_inode: false,
+        },
+        ArchiveNode::Dir(children) => {
+            let mut dir_info: Vec<FileInfo> = children
+                .into_iter()
+                .map(|(child_name, child_node)| {
+                    archive_node_to_file_info(
+                        &child_name,
+                        child_node,
+                        &path,
+                        depth + 1,
+                        sort_key,
+                        sort_ascending,
+                        dirs_first,
+                    )
+                })
+                .collect();
+
+            let mut total_size = 0;
+            let mut most_recent = time::UNIX_EPOCH;
+            for child in &dir_info {
+                total_size += child.size;
+                if child.modified > most_recent {
+                    most_recent = child.modified;
+                }
+            }
+            sort_entries(&mut dir_info, sort_key, sort_ascending, dirs_first);
+
+            FileInfo {
+                path,
+                depth,
+                file_type: ItemType::ArchiveMemberDir,
+                size: total_size,
+                modified: most_recent,
+                children: Some(dir_info),
+                accessible: true,
+                is_duplicate_inode: false,
+            }
+        }
+    }
+}
+
+fn read_archive_entries(
+    path: &Path,
+    kind: ArchiveKind,
+) -> Result<BTreeMap<String, ArchiveNode>, Box<dyn Error>> {
+    let file = fs::File::open(path)?;
+    let reader: Box<dyn Read> = match kind {
+        ArchiveKind::Tar => Box::new(file),
+        ArchiveKind::TarGz => Box::new(flate2::read::GzDecoder::new(file)),
+        ArchiveKind::TarZst => Box::new(zstd::stream::read::Decoder::new(file)?),
+    };
+
+    let mut archive = tar::Archive::new(reader);
+    let mut root: BTreeMap<String, ArchiveNode> = BTreeMap::new();
+
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let size = entry.header().size()?;
+        let modified = entry
+            .header()
+            .mtime()
+            .ok()
+            .and_then(|secs| time::UNIX_EPOCH.checked_add(time::Duration::from_secs(secs)))
+            .unwrap_or(time::UNIX_EPOCH);
+
+        let is_dir = entry.header().entry_type().is_dir();
+
+        let components: Vec<String> = entry
+            .path()?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+
+        insert_archive_entry(&mut root, &components, size, modified, is_dir);
+    }
+
+    Ok(root)
+}
+
+fn build_archive_info(
     path: &Path,
     depth: u8,
+    sort_key: SortKey,
     sort_ascending: bool,
-    skip_symlinks: bool,
-    parent_is_symlink: bool,
-    all_file_info: &mut Vec<FileInfo>,
-) {
-    let md = if skip_symlinks {
+    dirs_first: bool,
+) -> Option<FileInfo> {
+    let kind = archive_kind(path)?;
+    let root = read_archive_entries(path, kind).ok()?;
+
+    let mut dir_info: Vec<FileInfo> = root
+        .into_iter()
+        .map(|(name, node)| {
+            archive_node_to_file_info(
+                &name,
+                node,
+                path,
+                depth + 1,
+                sort_key,
+                sort_ascending,
+                dirs_first,
+            )
+        })
+        .collect();
+
+    let mut total_size = 0;
+    let mut most_recent = time::UNIX_EPOCH;
+    for child in &dir_info {
+        total_size += child.size;
+        if child.modified > most_recent {
+            most_recent = child.modified;
+        }
+    }
+    sort_entries(&mut dir_info, sort_key, sort_ascending, dirs_first);
+
+    Some(FileInfo {
+        path: PathBuf::from(path),
+        depth,
+        file_type: ItemType::Archive,
+        size: total_size,
+        modified: most_recent,
+        children: Some(dir_info),
+        accessible: true,
+        is_duplicate_inode: false,
+    })
+}
+
+fn sort_key_cmp(a: &FileInfo, b: &FileInfo, sort_key: SortKey) -> std::cmp::Ordering {
+    match sort_key {
+        SortKey::Size => a.size.cmp(&b.size),
+        SortKey::Time => a.modified.cmp(&b.modified),
+        SortKey::Name => a.pretty_path().cmp(b.pretty_path()),
+    }
+}
+
+fn sort_entries(entries: &mut [FileInfo], sort_key: SortKey, sort_ascending: bool, dirs_first: bool) {
+    entries.sort_by(|a, b| {
+        if dirs_first {
+            let a_is_dir = matches!(a.file_type, ItemType::Dir | ItemType::ArchiveMemberDir);
+            let b_is_dir = matches!(b.file_type, ItemType::Dir | ItemType::ArchiveMemberDir);
+            if a_is_dir != b_is_dir {
+                return b_is_dir.cmp(&a_is_dir);
+            }
+        }
+
+        let ord = sort_key_cmp(a, b, sort_key);
+        if sort_ascending {
+            ord
+        } else {
+            ord.reverse()
+        }
+    });
+}
+
+pub struct WalkOptions<'a> {
+    pub sort_ascending: bool,
+    pub skip_symlinks: bool,
+    pub usage: bool,
+    pub seen: &'a Mutex<HashSet<(u64, u64)>>,
+    pub no_hidden: bool,
+    pub excludes: &'a Option<GlobSet>,
+    pub sort_key: SortKey,
+    pub dirs_first: bool,
+    pub archives: bool,
+}
+
+pub fn walk(path: &Path, depth: u8, parent_is_symlink: bool, opts: &WalkOptions) -> Option<FileInfo> {
+    let md = if opts.skip_symlinks {
         fs::symlink_metadata(path)
     } else {
         fs::metadata(path)
@@ -282,7 +693,7 @@ pub fn walk(
         Ok(attr) => attr,
         Err(_) => {
             // println!("skipping {:#?}", path);
-            return;
+            return None;
         }
     };
 
@@ -293,16 +704,21 @@ pub fn walk(
     };
 
     if attr.is_file() {
-        let fi = get_file_info(path, depth, &attr, _parent_is_symlink).unwrap();
-        all_file_info.push(fi);
+        if opts.archives {
+            if let Some(archive_info) = build_archive_info(
+                path,
+                depth,
+                opts.sort_key,
+                opts.sort_ascending,
+                opts.dirs_first,
+            ) {
+                return Some(archive_info);
+            }
+        }
+        let fi =
+            get_file_info(path, depth, &attr, _parent_is_symlink, opts.usage, opts.seen).unwrap();
+        Some(fi)
     } else if attr.is_dir() {
-        let parent_info_idx = all_file_info.len();
-
-        // variables to accumulate info about this dir
-        let mut dir_info: Vec<FileInfo> = Vec::new();
-        let mut total_size: u64 = 0;
-        let mut most_recent: time::SystemTime = time::UNIX_EPOCH;
-
         // other info about the dir
         let p = PathBuf::from(&path.to_str().unwrap());
         let ft = if _parent_is_symlink {
@@ -312,42 +728,43 @@ pub fn walk(
         };
 
         let dir_iter_result = fs::read_dir(path);
-        let accessible = match dir_iter_result {
-            Err(_) => false,
+        let (mut dir_info, accessible) = match dir_iter_result {
+            Err(_) => (Vec::new(), false),
             Ok(dir_iter) => {
-                for entry in dir_iter {
-                    let item: fs::DirEntry = entry.unwrap();
-
-                    walk(
-                        &item.path(),
-                        depth + 1,
-                        sort_ascending,
-                        skip_symlinks,
-                        _parent_is_symlink,
-                        &mut dir_info,
-                    );
+                let entries: Vec<PathBuf> = dir_iter
+                    .map(|entry| entry.unwrap().path())
+                    .filter(|child_path| !is_excluded(child_path, opts.no_hidden, opts.excludes))
+                    .collect();
 
-                    let summarised_fi = dir_info.last().unwrap();
-                    total_size += summarised_fi.size;
+                let children: Vec<FileInfo> = entries
+                    .par_iter()
+                    .filter_map(|child_path| walk(child_path, depth + 1, _parent_is_symlink, opts))
+                    .collect();
 
-                    if summarised_fi.modified > most_recent {
-                        most_recent = summarised_fi.modified;
-                    }
-                }
-                true // dir is accessible
+                (children, true) // dir is accessible
             }
         };
 
-        // make FileInfo with summarised dir
-        total_size += attr.len();
+        let mut total_size: u64 = file_size(&attr, opts.usage);
+        let mut most_recent: time::SystemTime = time::UNIX_EPOCH;
+        for summarised_fi in &dir_info {
+            if !summarised_fi.is_duplicate_inode {
+                total_size += summarised_fi.size;
+            }
 
-        if sort_ascending {
-            dir_info.sort_by(|a, b| a.size.cmp(&b.size));
-        } else {
-            dir_info.sort_by(|a, b| b.size.cmp(&a.size));
+            if summarised_fi.modified > most_recent {
+                most_recent = summarised_fi.modified;
+            }
         }
 
-        let total_info = FileInfo {
+        sort_entries(
+            &mut dir_info,
+            opts.sort_key,
+            opts.sort_ascending,
+            opts.dirs_first,
+        );
+
+        Some(FileInfo {
             path: p,
             depth: depth,
             file_type: ft,
@@ -355,25 +772,100 @@ pub fn walk(
             modified: most_recent,
             children: Some(dir_info),
             accessible: accessible,
-        };
+            is_duplicate_inode: false,
+        })
+    } else {
+        None
+    }
+}
+
+fn build_excludes(patterns: &[Glob]) -> Option<GlobSet> {
+    if patterns.is_empty() {
+        return None;
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(pattern.clone());
+    }
+    Some(builder.build().unwrap())
+}
+
+// Applied bottom-up. Each child is judged by its total size, which
+// aggregation leaves unchanged, so a directory below the threshold is
+// collapsed together with everything under it.
+fn aggregate_small_entries(
+    info: FileInfo,
+    threshold: u64,
+    sort_key: SortKey,
+    sort_ascending: bool,
+    dirs_first: bool,
+) -> FileInfo {
+    let children = match info.children {
+        None => return info,
+        Some(children) => children,
+    };
+
+    let mut kept = Vec::new();
+    let mut small_total: u64 = 0;
+    let mut small_count: usize = 0;
+
+    for child in children {
+        let child = aggregate_small_entries(child, threshold, sort_key, sort_ascending, dirs_first);
+        if child.size < threshold {
+            small_total += child.size;
+            small_count += 1;
+        } else {
+            kept.push(child);
+        }
+    }
 
-        // insert parent dir entry above it's contents
-        all_file_info.insert(parent_info_idx, total_info);
+    if small_count > 0 {
+        kept.push(FileInfo {
+            path: PathBuf::from(format!("<{} files>", small_count)),
+            depth: info.depth + 1,
+            file_type: ItemType::File,
+            size: small_total,
+            modified: time::UNIX_EPOCH,
+            children: None,
+            accessible: true,
+            is_duplicate_inode: false,
+        });
+        sort_entries(&mut kept, sort_key, sort_ascending, dirs_first);
+    }
+
+    FileInfo {
+        children: Some(kept),
+        ..info
     }
 }
 
 pub fn list_files(cli: Cli) {
     let path = PathBuf::from(cli.file);
+    let excludes = build_excludes(&cli.exclude);
+
+    let seen: Mutex<HashSet<(u64, u64)>> = Mutex::new(HashSet::new());
+    let opts = WalkOptions {
+        sort_ascending: cli.ascending,
+        skip_symlinks: cli.skip_symlinks,
+        usage: cli.usage,
+        seen: &seen,
+        no_hidden: cli.no_hidden,
+        excludes: &excludes,
+        sort_key: cli.sort,
+        dirs_first: cli.dirs_first,
+        archives: cli.archives,
+    };
+    let root = walk(&path, 1, false, &opts);
 
-    let mut all_file_info: Vec<FileInfo> = Vec::new();
-    walk(
-        &path,
-        1,
-        cli.ascending,
-        cli.skip_symlinks,
-        false,
-        &mut all_file_info,
-    );
+    let root = match cli.aggregate {
+        Some(threshold) => root.map(|info| {
+            aggregate_small_entries(info, threshold, cli.sort, cli.ascending, cli.dirs_first)
+        }),
+        None => root,
+    };
+
+    let all_file_info: Vec<FileInfo> = root.into_iter().collect();
 
     print_results(
         &all_file_info,
@@ -381,5 +873,88 @@ pub fn list_files(cli: Cli) {
         cli.si,
         cli.time,
         cli.max_depth,
+        cli.bars,
+        None,
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_size_threshold_plain_bytes() {
+        assert_eq!(parse_size_threshold("512").unwrap(), 512);
+    }
+
+    #[test]
+    fn parse_size_threshold_suffixes() {
+        assert_eq!(parse_size_threshold("10K").unwrap(), 10 * 1024);
+        assert_eq!(parse_size_threshold("4m").unwrap(), 4 * 1024 * 1024);
+        assert_eq!(parse_size_threshold("1G").unwrap(), 1024 * 1024 * 1024);
+    }
+
+    #[test]
+    fn parse_size_threshold_rejects_garbage() {
+        assert!(parse_size_threshold("banana").is_err());
+    }
+
+    #[test]
+    fn archive_kind_recognises_tar_variants() {
+        assert!(matches!(
+            archive_kind(Path::new("foo.tar")),
+            Some(ArchiveKind::Tar)
+        ));
+        assert!(matches!(
+            archive_kind(Path::new("foo.tar.gz")),
+            Some(ArchiveKind::TarGz)
+        ));
+        assert!(matches!(
+            archive_kind(Path::new("foo.tgz")),
+            Some(ArchiveKind::TarGz)
+        ));
+        assert!(matches!(
+            archive_kind(Path::new("foo.tar.zst")),
+            Some(ArchiveKind::TarZst)
+        ));
+    }
+
+    #[test]
+    fn archive_kind_ignores_non_archives() {
+        assert!(archive_kind(Path::new("foo.txt")).is_none());
+    }
+
+    fn file_info(name: &str, size: u64, modified_secs: u64) -> FileInfo {
+        FileInfo {
+            path: PathBuf::from(name),
+            depth: 0,
+            file_type: ItemType::File,
+            size,
+            modified: time::UNIX_EPOCH + time::Duration::from_secs(modified_secs),
+            children: None,
+            accessible: true,
+            is_duplicate_inode: false,
+        }
+    }
+
+    #[test]
+    fn sort_key_cmp_by_size() {
+        let a = file_info("a", 1, 0);
+        let b = file_info("b", 2, 0);
+        assert_eq!(sort_key_cmp(&a, &b, SortKey::Size), std::cmp::Ordering::Less);
+    }
+
+    #[test]
+    fn sort_key_cmp_by_time() {
+        let a = file_info("a", 0, 1);
+        let b = file_info("b", 0, 2);
+        assert_eq!(sort_key_cmp(&a, &b, SortKey::Time), std::cmp::Ordering::Less);
+    }
+
+    #[test]
+    fn sort_key_cmp_by_name() {
+        let a = file_info("a", 0, 0);
+        let b = file_info("b", 0, 0);
+        assert_eq!(sort_key_cmp(&a, &b, SortKey::Name), std::cmp::Ordering::Less);
+    }
+}